@@ -1,15 +1,18 @@
 //! SRP client implementation.
-//! 
+//!
 //! # Usage
 //! First create SRP client struct by passing to it SRP parameters (shared
-//! between client and server) and RNG instance (OS RNG is recommended):
-//! 
+//! between client and server) and the client's ephemeral secret `a`. `a`
+//! should be a CSPRNG-generated value at least 256 bits long; `with_rng`
+//! is a convenience constructor that generates it for you (OS RNG is
+//! recommended):
+//!
 //! ```ignore
 //! let srp_params = SrpParams{n, g, k};
 //! let mut rng = rand::os::OsRng::new().unwrap();
-//! let client = SrpClient::<Sha256>::new(&srp_params, &mut rng);
+//! let client = SrpClient::<Sha256>::with_rng(&srp_params, &mut rng);
 //! ```
-//! 
+//!
 //! Next send handshake data (username and `a_pub`) to the server and receive
 //! `salt` and `b_pub`:
 //! 
@@ -60,7 +63,7 @@ use num::{BigUint, Zero};
 use digest::Digest;
 use generic_array::GenericArray;
 
-use tools::powm;
+use tools::{pad, powm};
 use types::{SrpAuthError, SrpParams};
 
 /// SRP client state before handshake with the server.
@@ -99,22 +102,54 @@ pub fn srp6a_private_key<D: Digest>(username: &[u8], password: &[u8],
     d.result()
 }
 
+/// Minimal length of the ephemeral secret `a`, in bytes. SRP-6a recommends
+/// at least 256 bits of entropy for the client's private exponent.
+const MIN_A_LEN: usize = 32;
+
 impl<'a, D: Digest> SrpClient<'a, D> {
-    /// Create new SRP client instance.
-    pub fn new<R: Rng>(params: &'a SrpParams, rng: &mut R) -> Self {
-        let l = params.n.bits()/8; 
-        let buf = rng.gen_iter::<u8>().take(l).collect::<Vec<u8>>();
-        let a = BigUint::from_bytes_le(&buf);
+    /// Create new SRP client instance from a caller-supplied ephemeral
+    /// secret `a`. `a` should come from a CSPRNG; this constructor leaves
+    /// entropy generation entirely up to the caller, which also makes it
+    /// possible to run fixed test vectors.
+    ///
+    /// Returns `SrpAuthError` if `a` is shorter than 256 bits, or if the
+    /// resulting `a_pub` is congruent to zero modulo `N` — the client-side
+    /// analogue of the safeguard against a malicious `b_pub` already
+    /// performed in `process_reply`. Both parties must abort the handshake
+    /// in that case.
+    pub fn new(a: &[u8], params: &'a SrpParams) -> Result<Self, SrpAuthError> {
+        if a.len() < MIN_A_LEN {
+            return Err(SrpAuthError{ description: "Ephemeral secret `a` is too short" })
+        }
+
+        let a = BigUint::from_bytes_be(a);
         let a_pub = params.powm(&a);
 
-        Self { params, a, a_pub, d: Default::default() }
+        if &a_pub % &params.n == BigUint::zero() {
+            return Err(SrpAuthError{ description: "Degenerate a_pub value" })
+        }
+
+        Ok(Self { params, a, a_pub, d: Default::default() })
+    }
+
+    /// Create new SRP client instance, generating the ephemeral secret `a`
+    /// using `rng`. Regenerates `a` on the rare chance it produces a
+    /// degenerate `a_pub`.
+    pub fn with_rng<R: Rng>(params: &'a SrpParams, rng: &mut R) -> Self {
+        let l = ::std::cmp::max(params.n.bits()/8, MIN_A_LEN);
+        loop {
+            let buf = rng.gen_iter::<u8>().take(l).collect::<Vec<u8>>();
+            if let Ok(client) = Self::new(&buf, params) {
+                return client;
+            }
+        }
     }
 
     /// Get password verfier for user registration on the server
     pub fn get_password_verifier(&self, private_key: &[u8]) -> Vec<u8> {
-        let x = BigUint::from_bytes_le(&private_key);
+        let x = BigUint::from_bytes_be(private_key);
         let v = self.params.powm(&x);
-        v.to_bytes_le()
+        v.to_bytes_be()
     }
 
     fn calc_key(&self, b_pub: &BigUint, x: &BigUint, u: &BigUint)
@@ -130,34 +165,47 @@ impl<'a, D: Digest> SrpClient<'a, D> {
         };
         // S = |B - kg^x| ^ (a + ux)
         let s = powm(&v, &(&self.a + (u*x) % n ), n);
-        D::digest(&s.to_bytes_le())
+        D::digest(&pad(&s.to_bytes_be(), n.to_bytes_be().len()))
     }
 
     /// Process server reply to the handshake.
     pub fn process_reply(self, private_key: &[u8], b_pub: &[u8])
         -> Result<SrpClientVerifier<D>, SrpAuthError>
     {
+        let n_len = self.params.n.to_bytes_be().len();
+
+        // Re-derive B's canonical byte representation from the parsed
+        // integer: the wire bytes may carry non-canonical leading zeroes
+        // (or, if larger than N, aren't a valid field element at all), and
+        // PAD assumes a fixed-size field of exactly `n_len` bytes.
+        let b_pub = BigUint::from_bytes_be(b_pub);
+        let b_pub_bytes = b_pub.to_bytes_be();
+        if b_pub_bytes.len() > n_len {
+            return Err(SrpAuthError{ description: "b_pub is larger than N" })
+        }
+
+        let a_pub_padded = pad(&self.a_pub.to_bytes_be(), n_len);
+        let b_pub_padded = pad(&b_pub_bytes, n_len);
+
         let u = {
             let mut d = D::new();
-            d.input(&self.a_pub.to_bytes_le());
-            d.input(b_pub);
-            BigUint::from_bytes_le(&d.result())
+            d.input(&a_pub_padded);
+            d.input(&b_pub_padded);
+            BigUint::from_bytes_be(&d.result())
         };
 
-        let b_pub = BigUint::from_bytes_le(b_pub);
-
         // Safeguard against malicious B
         if &b_pub % &self.params.n == BigUint::zero() {
             return Err(SrpAuthError{ description: "Malicious b_pub value" })
         }
 
-        let x = BigUint::from_bytes_le(&private_key);
+        let x = BigUint::from_bytes_be(private_key);
         let key = self.calc_key(&b_pub, &x, &u);
         // M1 = H(A, B, K)
         let proof = {
             let mut d = D::new();
-            d.input(&self.a_pub.to_bytes_le());
-            d.input(&b_pub.to_bytes_le());
+            d.input(&a_pub_padded);
+            d.input(&b_pub_padded);
             d.input(&key);
             d.result()
         };
@@ -165,22 +213,22 @@ impl<'a, D: Digest> SrpClient<'a, D> {
         // M2 = H(A, M1, K)
         let server_proof = {
             let mut d = D::new();
-            d.input(&self.a_pub.to_bytes_le());
+            d.input(&a_pub_padded);
             d.input(&proof);
             d.input(&key);
             d.result()
         };
 
         Ok(SrpClientVerifier {
-            proof: proof,
-            server_proof: server_proof,
-            key: key,
+            proof,
+            server_proof,
+            key,
         })
     }
 
     /// Get public ephemeral value for handshaking with the server.
     pub fn get_a_pub(&self) -> Vec<u8> {
-        self.a_pub.to_bytes_le()
+        self.a_pub.to_bytes_be()
     }
 }
 
@@ -208,4 +256,94 @@ impl<D: Digest> SrpClientVerifier<D> {
             Ok(self.key)
         }
     }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate sha2;
+
+    use self::sha2::Sha256;
+
+    use groups::G_2048;
+    use super::*;
+
+    // There is no server implementation in this crate yet, so the server
+    // side of SRP-6a is reimplemented independently here (rather than
+    // reusing client-side helpers) to catch regressions in the wire
+    // encoding, PAD, or key derivation that a test calling only into the
+    // client would miss.
+    fn server_calc_key(params: &SrpParams, v: &BigUint, b: &BigUint, a_pub: &BigUint, u: &BigUint)
+        -> GenericArray<u8, <Sha256 as digest::FixedOutput>::OutputSize>
+    {
+        // S = (A * v^u) ^ b mod N
+        let s = powm(&(a_pub * powm(v, u, &params.n)), b, &params.n);
+        let n_len = params.n.to_bytes_be().len();
+        Sha256::digest(&pad(&s.to_bytes_be(), n_len))
+    }
+
+    #[test]
+    fn client_server_handshake_round_trip() {
+        let params = SrpParams::from_group::<Sha256>(&G_2048);
+
+        let username = b"alice";
+        let password = b"password123";
+        let salt = b"some-salt";
+        let private_key = srp6a_private_key::<Sha256>(username, password, salt);
+        let v = params.powm(&BigUint::from_bytes_be(&private_key));
+
+        let a = [0x42u8; 32];
+        let client = SrpClient::<Sha256>::new(&a, &params).unwrap();
+        let a_pub = BigUint::from_bytes_be(&client.get_a_pub());
+
+        let b = BigUint::from_bytes_be(&[0x24u8; 32]);
+        let b_pub = (&params.k * &v + params.powm(&b)) % &params.n;
+
+        let n_len = params.n.to_bytes_be().len();
+        let u = {
+            let mut d = Sha256::new();
+            d.input(&pad(&a_pub.to_bytes_be(), n_len));
+            d.input(&pad(&b_pub.to_bytes_be(), n_len));
+            BigUint::from_bytes_be(&d.result())
+        };
+
+        let server_key = server_calc_key(&params, &v, &b, &a_pub, &u);
+
+        let verifier = client.process_reply(&private_key, &b_pub.to_bytes_be()).unwrap();
+        assert_eq!(verifier.get_key(), server_key);
+    }
+
+    #[test]
+    fn rejects_short_ephemeral_secret() {
+        let params = SrpParams::from_group::<Sha256>(&G_2048);
+        let short_a = [0x01u8; MIN_A_LEN - 1];
+        assert!(SrpClient::<Sha256>::new(&short_a, &params).is_err());
+    }
+
+    #[test]
+    fn rejects_degenerate_a_pub() {
+        // g ≡ 0 (mod n) makes every a_pub = g^a mod n degenerate, the
+        // client-side case the safeguard already applied to b_pub in
+        // process_reply is supposed to catch.
+        let params = SrpParams {
+            n: BigUint::from_bytes_be(&[97]),
+            g: BigUint::from_bytes_be(&[97]),
+            k: BigUint::from_bytes_be(&[1]),
+        };
+        let a = [0x01u8; MIN_A_LEN];
+        assert!(SrpClient::<Sha256>::new(&a, &params).is_err());
+    }
+
+    #[test]
+    fn with_rng_terminates_for_a_group_smaller_than_min_a_len() {
+        // Regression test for the with_rng infinite loop: a non-degenerate
+        // group much smaller than MIN_A_LEN used to make every generated `a`
+        // fail the length check forever.
+        let params = SrpParams {
+            n: BigUint::from_bytes_be(&[97]),
+            g: BigUint::from_bytes_be(&[5]),
+            k: BigUint::from_bytes_be(&[1]),
+        };
+        let mut rng = rand::thread_rng();
+        SrpClient::<Sha256>::with_rng(&params, &mut rng);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,15 @@
+//! Pure Rust implementation of the Secure Remote Password (SRP) protocol
+//! version 6a, as specified in [RFC 5054](https://tools.ietf.org/html/rfc5054).
+extern crate digest;
+extern crate generic_array;
+#[macro_use]
+extern crate lazy_static;
+extern crate num;
+extern crate rand;
+
+pub mod client;
+pub mod groups;
+mod tools;
+pub mod types;
+
+pub use types::{SrpAuthError, SrpParams};
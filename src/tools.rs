@@ -0,0 +1,22 @@
+//! Miscellaneous helpers shared between the client and server implementations.
+use num::BigUint;
+
+/// Compute `base^exponent mod modulus`.
+pub fn powm(base: &BigUint, exponent: &BigUint, modulus: &BigUint) -> BigUint {
+    base.modpow(exponent, modulus)
+}
+
+/// RFC 5054 `PAD`: left-pad `data` with zero bytes up to `len`.
+///
+/// Used whenever a big-endian integer is about to be hashed, so that two
+/// peers who picked different byte-lengths for the same integer still
+/// derive the same digest.
+pub(crate) fn pad(data: &[u8], len: usize) -> Vec<u8> {
+    if data.len() >= len {
+        data.to_vec()
+    } else {
+        let mut buf = vec![0u8; len - data.len()];
+        buf.extend_from_slice(data);
+        buf
+    }
+}
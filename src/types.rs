@@ -0,0 +1,54 @@
+//! Common types used by the SRP client and server.
+use std::error;
+use std::fmt;
+
+use digest::Digest;
+use num::BigUint;
+
+use groups::SrpGroup;
+use tools::powm;
+
+/// Error happening during SRP authentication.
+#[derive(Debug, Clone)]
+pub struct SrpAuthError {
+    pub(crate) description: &'static str,
+}
+
+impl fmt::Display for SrpAuthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SRP authentication error: {}", self.description)
+    }
+}
+
+impl error::Error for SrpAuthError {
+    fn description(&self) -> &str {
+        self.description
+    }
+}
+
+/// Parameters of the SRP protocol shared between the client and the server:
+/// the group modulus `n`, the generator `g` and the multiplier `k`.
+#[derive(Debug, Clone)]
+pub struct SrpParams {
+    pub n: BigUint,
+    pub g: BigUint,
+    pub k: BigUint,
+}
+
+impl SrpParams {
+    /// Compute `g^x mod n`.
+    pub fn powm(&self, x: &BigUint) -> BigUint {
+        powm(&self.g, x, &self.n)
+    }
+
+    /// Build `SrpParams` out of a standard `SrpGroup` (see the `groups`
+    /// module), computing the SRP-6a multiplier `k = H(N | PAD(g))` for the
+    /// chosen digest so callers never have to assemble `n`/`g`/`k` by hand.
+    pub fn from_group<D: Digest>(group: &SrpGroup) -> SrpParams {
+        SrpParams {
+            n: group.n.clone(),
+            g: group.g.clone(),
+            k: group.compute_k::<D>(),
+        }
+    }
+}